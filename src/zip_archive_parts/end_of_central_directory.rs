@@ -0,0 +1,107 @@
+//! Writing of the records that close a ZIP archive once every entry and the central directory
+//! have been emitted.
+//!
+//! A classic archive ends with a single End Of Central Directory record whose counts, sizes and
+//! offsets are 16- or 32-bit. As soon as an archive holds more than `0xFFFF` entries, or its
+//! central directory begins past 4 GiB, those fields overflow; the Zip64 End Of Central Directory
+//! record and its locator are then written ahead of the classic record, which keeps
+//! `0xFFFF`/`0xFFFFFFFF` sentinels in the overflowed fields to send readers to the 64-bit copies.
+
+use std::io::{self, Write};
+
+const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+const ZIP64_EOCD_SIGNATURE: u32 = 0x0606_4b50;
+const ZIP64_EOCD_LOCATOR_SIGNATURE: u32 = 0x0706_4b50;
+
+/// Version needed to extract (4.5) stored whenever Zip64 records are written.
+const ZIP64_VERSION_NEEDED: u16 = 45;
+
+/// Writes the end-of-central-directory record(s) for an archive whose local entries and central
+/// directory have already been written to `sink`.
+///
+/// `entry_count` is the number of central-directory records, `central_size` their combined byte
+/// length and `central_offset` the position at which they begin. The Zip64 record and locator are
+/// emitted first whenever any of those values exceeds what the classic record can hold, or when
+/// `force_zip64` is set because an individual entry already required a Zip64 extra field.
+pub fn write_end_of_central_directory<W: Write>(
+    sink: &mut W,
+    entry_count: u64,
+    central_size: u64,
+    central_offset: u64,
+    force_zip64: bool,
+) -> io::Result<()> {
+    let needs_zip64 = force_zip64
+        || entry_count > u16::MAX as u64
+        || central_size > u32::MAX as u64
+        || central_offset > u32::MAX as u64;
+
+    if needs_zip64 {
+        // The Zip64 record sits immediately after the central directory, and the locator that
+        // follows it records where it began so readers can find it from the tail of the file.
+        let zip64_eocd_offset = central_offset + central_size;
+
+        sink.write_all(&ZIP64_EOCD_SIGNATURE.to_le_bytes())?;
+        // Size of the record counting every byte that follows this field: the fixed 44-byte
+        // remainder, with no extensible data sector.
+        sink.write_all(&44u64.to_le_bytes())?;
+        sink.write_all(&ZIP64_VERSION_NEEDED.to_le_bytes())?; // version made by
+        sink.write_all(&ZIP64_VERSION_NEEDED.to_le_bytes())?; // version needed to extract
+        sink.write_all(&0u32.to_le_bytes())?; // number of this disk
+        sink.write_all(&0u32.to_le_bytes())?; // disk with the start of the central directory
+        sink.write_all(&entry_count.to_le_bytes())?; // entries on this disk
+        sink.write_all(&entry_count.to_le_bytes())?; // total entries
+        sink.write_all(&central_size.to_le_bytes())?;
+        sink.write_all(&central_offset.to_le_bytes())?;
+
+        sink.write_all(&ZIP64_EOCD_LOCATOR_SIGNATURE.to_le_bytes())?;
+        sink.write_all(&0u32.to_le_bytes())?; // disk with the Zip64 EOCD record
+        sink.write_all(&zip64_eocd_offset.to_le_bytes())?;
+        sink.write_all(&1u32.to_le_bytes())?; // total number of disks
+    }
+
+    // Classic record. Any value that was promoted to the Zip64 record is replaced by its
+    // all-ones sentinel here.
+    let disk_entries = entry_count.min(u16::MAX as u64) as u16;
+    let size = central_size.min(u32::MAX as u64) as u32;
+    let offset = central_offset.min(u32::MAX as u64) as u32;
+
+    sink.write_all(&EOCD_SIGNATURE.to_le_bytes())?;
+    sink.write_all(&0u16.to_le_bytes())?; // number of this disk
+    sink.write_all(&0u16.to_le_bytes())?; // disk with the start of the central directory
+    sink.write_all(&disk_entries.to_le_bytes())?; // entries on this disk
+    sink.write_all(&disk_entries.to_le_bytes())?; // total entries
+    sink.write_all(&size.to_le_bytes())?;
+    sink.write_all(&offset.to_le_bytes())?;
+    sink.write_all(&0u16.to_le_bytes())?; // archive comment length
+
+    Ok(())
+}
+
+/// Builds the `0x0001` Zip64 extra field for a *central-directory* record.
+///
+/// Unlike the local-header copy produced by [`ExtraFields::zip64`](super::extra_field::ExtraFields::zip64),
+/// the central record may also need to carry the 64-bit local-header offset. Each value is
+/// present only when its 32-bit central field was set to the `0xFFFFFFFF` sentinel, and the
+/// fields appear in the fixed order uncompressed size, compressed size, local-header offset.
+pub fn central_zip64_extra(
+    uncompressed_size: u64,
+    compressed_size: u64,
+    local_header_offset: u64,
+) -> Vec<u8> {
+    let mut body = Vec::new();
+    if uncompressed_size > u32::MAX as u64 {
+        body.extend_from_slice(&uncompressed_size.to_le_bytes());
+    }
+    if compressed_size > u32::MAX as u64 {
+        body.extend_from_slice(&compressed_size.to_le_bytes());
+    }
+    if local_header_offset > u32::MAX as u64 {
+        body.extend_from_slice(&local_header_offset.to_le_bytes());
+    }
+
+    let mut out = Vec::with_capacity(body.len() + 4);
+    out.extend_from_slice(&0x0001u16.to_le_bytes());
+    out.extend_from_slice(&(body.len() as u16).to_le_bytes());
+    out.extend_from_slice(&body);
+    out
+}
@@ -1,7 +1,7 @@
 use std::{
     borrow::Cow,
     fs::{File, Metadata},
-    io::Read,
+    io::{Read, Write},
     panic::{RefUnwindSafe, UnwindSafe},
     path::Path,
 };
@@ -19,6 +19,11 @@ pub enum ZipJobOrigin<'d, 'p, 'r> {
     Directory,
     Filesystem {
         path: Cow<'p, Path>,
+        /// When `false`, a symlink is archived as a symlink (its target path stored verbatim
+        /// with the `S_IFLNK` mode) rather than as a copy of its target. No effect on
+        /// platforms without symlink support, where the target is always followed, nor on
+        /// [`ZipJob::into_stream`], which always follows symlinks.
+        follow_symlinks: bool,
     },
     RawData(Cow<'d, [u8]>),
     Reader(
@@ -27,13 +32,157 @@ pub enum ZipJobOrigin<'d, 'p, 'r> {
     ),
 }
 
+/// Key strength for WinZip AE-2 encryption, as stored in the `0x9901` extra field.
+#[cfg(feature = "aes")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AesStrength {
+    Aes128,
+    Aes192,
+    Aes256,
+}
+
+#[cfg(feature = "aes")]
+impl AesStrength {
+    /// Length of the AES key in bytes.
+    const fn key_len(self) -> usize {
+        match self {
+            Self::Aes128 => 16,
+            Self::Aes192 => 24,
+            Self::Aes256 => 32,
+        }
+    }
+
+    /// Strength byte recorded in the `0x9901` extra field.
+    const fn id(self) -> u8 {
+        match self {
+            Self::Aes128 => 0x01,
+            Self::Aes192 => 0x02,
+            Self::Aes256 => 0x03,
+        }
+    }
+}
+
+/// Per-entry WinZip AE-2 encryption settings.
+#[cfg(feature = "aes")]
+#[derive(Debug, Clone)]
+pub struct AesEncryption {
+    pub password: Vec<u8>,
+    pub strength: AesStrength,
+}
+
+/// CRC-32 (ISO-HDLC) lookup table, used by the traditional PKWARE key schedule.
+#[cfg(feature = "zipcrypto")]
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 { 0xedb8_8320 ^ (c >> 1) } else { c >> 1 };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+/// The three 32-bit keys of the traditional PKWARE stream cipher.
+#[cfg(feature = "zipcrypto")]
+struct ZipCryptoKeys {
+    key0: u32,
+    key1: u32,
+    key2: u32,
+}
+
+#[cfg(feature = "zipcrypto")]
+impl ZipCryptoKeys {
+    fn new(password: &[u8]) -> Self {
+        let mut keys = Self {
+            key0: 0x1234_5678,
+            key1: 0x2345_6789,
+            key2: 0x3456_7890,
+        };
+        for &byte in password {
+            keys.update(byte);
+        }
+        keys
+    }
+
+    #[inline]
+    fn crc32(crc: u32, byte: u8) -> u32 {
+        (crc >> 8) ^ CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize]
+    }
+
+    #[inline]
+    fn update(&mut self, byte: u8) {
+        self.key0 = Self::crc32(self.key0, byte);
+        self.key1 = (self.key1.wrapping_add(self.key0 & 0xff))
+            .wrapping_mul(134_775_813)
+            .wrapping_add(1);
+        self.key2 = Self::crc32(self.key2, (self.key1 >> 24) as u8);
+    }
+
+    #[inline]
+    fn stream_byte(&self) -> u8 {
+        let t = (self.key2 | 3) as u16;
+        (((t | 2).wrapping_mul(t ^ 1)) >> 8) as u8
+    }
+}
+
 #[derive(Debug)]
 struct FileDigest {
     data: Vec<u8>,
-    uncompressed_size: u32,
+    uncompressed_size: u64,
     crc: u32,
 }
 
+/// CRC and sizes of a streamed entry, emitted after its data as a trailing data descriptor
+/// and backfilled into the central directory. The entry's local header is written with these
+/// fields zeroed and general-purpose bit 3 set.
+#[derive(Debug, Clone, Copy)]
+pub struct ZipFileDataDescriptor {
+    pub crc: u32,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+}
+
+/// Wraps a reader to count the bytes read through it in a full `u64`. `flate2::Crc::amount()`
+/// is only a `u32` and wraps at 4 GiB, so it cannot be trusted for the uncompressed size of
+/// the large inputs these paths exist to handle.
+struct CountingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read as u64;
+        Ok(read)
+    }
+}
+
+/// Wraps a writer to count the bytes written through it, used to learn the compressed size of
+/// a streamed entry whose encoder does not report it.
+struct CountingWriter<'w, W> {
+    inner: &'w mut W,
+    count: u64,
+}
+
+impl<W: Write> Write for CountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 #[derive(Debug)]
 pub struct ZipJob<'a, 'p, 'r> {
     pub data_origin: ZipJobOrigin<'a, 'p, 'r>,
@@ -45,6 +194,13 @@ pub struct ZipJob<'a, 'p, 'r> {
     pub compression_level: CompressionLevel,
     /// Ignored when [`data_origin`](Self::data_origin) is a [`ZipJobOrigin::Directory`]
     pub compression_type: CompressionType,
+    /// Optional WinZip AE-2 encryption. Ignored for [`ZipJobOrigin::Directory`].
+    #[cfg(feature = "aes")]
+    pub encryption: Option<AesEncryption>,
+    /// Optional traditional PKWARE (ZipCrypto) password. Ignored for
+    /// [`ZipJobOrigin::Directory`].
+    #[cfg(feature = "zipcrypto")]
+    pub zipcrypto: Option<Vec<u8>>,
 }
 
 impl ZipJob<'_, '_, '_> {
@@ -78,22 +234,52 @@ impl ZipJob<'_, '_, '_> {
 
     fn compress_file<R: Read>(
         source: R,
-        uncompressed_size_approx: Option<u32>,
+        uncompressed_size_approx: Option<u64>,
         compression_type: CompressionType,
         compression_level: CompressionLevel,
     ) -> std::io::Result<FileDigest> {
-        let mut crc_reader = CrcReader::new(source);
+        let mut crc_reader = CrcReader::new(CountingReader {
+            inner: source,
+            count: 0,
+        });
         let mut data = Vec::with_capacity(uncompressed_size_approx.unwrap_or(0) as usize);
-        let uncompressed_size = match compression_type {
+        let uncompressed_size: u64 = match compression_type {
             CompressionType::Deflate => {
                 let mut encoder = DeflateEncoder::new(&mut crc_reader, compression_level.into());
                 encoder.read_to_end(&mut data)?;
-                encoder.total_in() as usize
+                encoder.total_in()
+            }
+            #[cfg(feature = "bzip2")]
+            CompressionType::Bzip2 => {
+                let mut encoder =
+                    bzip2::read::BzEncoder::new(&mut crc_reader, compression_level.into());
+                encoder.read_to_end(&mut data)?;
+                encoder.total_in()
             }
-            CompressionType::Stored => crc_reader.read_to_end(&mut data)?,
+            #[cfg(feature = "zstd")]
+            CompressionType::Zstd => {
+                let mut encoder =
+                    zstd::stream::read::Encoder::new(&mut crc_reader, compression_level.into())?;
+                encoder.read_to_end(&mut data)?;
+                // zstd's reader does not expose the number of bytes consumed, so we take it from
+                // the counting reader, which has seen every uncompressed byte.
+                crc_reader.get_ref().count
+            }
+            #[cfg(feature = "zopfli")]
+            CompressionType::DeflateZopfli { iterations } => {
+                // Zopfli buffers the whole entry and runs several squeeze passes to find a
+                // smaller method-8 Deflate encoding; the readers need no changes because the
+                // output is still ordinary Deflate.
+                let options = zopfli::Options {
+                    iteration_count: std::num::NonZeroU64::new(iterations.into())
+                        .unwrap_or(std::num::NonZeroU64::new(15).unwrap()),
+                    ..Default::default()
+                };
+                zopfli::compress(options, zopfli::Format::Deflate, &mut crc_reader, &mut data)?;
+                crc_reader.get_ref().count
+            }
+            CompressionType::Stored => crc_reader.read_to_end(&mut data)? as u64,
         };
-        debug_assert!(uncompressed_size <= u32::MAX as usize);
-        let uncompressed_size = uncompressed_size as u32;
         data.shrink_to_fit();
         let crc = crc_reader.crc().sum();
         Ok(FileDigest {
@@ -103,6 +289,213 @@ impl ZipJob<'_, '_, '_> {
         })
     }
 
+    /// Compresses `source` straight into `sink` without buffering the whole entry, returning
+    /// the CRC together with the compressed and uncompressed sizes for the trailing data
+    /// descriptor.
+    fn compress_stream<R: Read, W: Write>(
+        source: R,
+        compression_type: CompressionType,
+        compression_level: CompressionLevel,
+        sink: &mut W,
+    ) -> std::io::Result<ZipFileDataDescriptor> {
+        let mut crc_reader = CrcReader::new(CountingReader {
+            inner: source,
+            count: 0,
+        });
+        let compressed_size = match compression_type {
+            CompressionType::Deflate => {
+                let mut encoder = DeflateEncoder::new(&mut crc_reader, compression_level.into());
+                std::io::copy(&mut encoder, sink)?
+            }
+            #[cfg(feature = "bzip2")]
+            CompressionType::Bzip2 => {
+                let mut encoder =
+                    bzip2::read::BzEncoder::new(&mut crc_reader, compression_level.into());
+                std::io::copy(&mut encoder, sink)?
+            }
+            #[cfg(feature = "zstd")]
+            CompressionType::Zstd => {
+                let mut encoder =
+                    zstd::stream::read::Encoder::new(&mut crc_reader, compression_level.into())?;
+                std::io::copy(&mut encoder, sink)?
+            }
+            #[cfg(feature = "zopfli")]
+            CompressionType::DeflateZopfli { iterations } => {
+                let options = zopfli::Options {
+                    iteration_count: std::num::NonZeroU64::new(iterations.into())
+                        .unwrap_or(std::num::NonZeroU64::new(15).unwrap()),
+                    ..Default::default()
+                };
+                let mut counter = CountingWriter {
+                    inner: sink,
+                    count: 0,
+                };
+                zopfli::compress(
+                    options,
+                    zopfli::Format::Deflate,
+                    &mut crc_reader,
+                    &mut counter,
+                )?;
+                counter.count
+            }
+            CompressionType::Stored => std::io::copy(&mut crc_reader, sink)?,
+        };
+        Ok(ZipFileDataDescriptor {
+            crc: crc_reader.crc().sum(),
+            compressed_size,
+            uncompressed_size: crc_reader.get_ref().count,
+        })
+    }
+
+    /// Applies the configured encryption (if any) to an already-compressed entry, returning the
+    /// transformed data, the CRC to store, the general-purpose flags to set, and the method id
+    /// to write into the header (`Some(99)` for AES, which replaces the real method).
+    ///
+    /// For AE-2 the data becomes `salt ‖ verifier ‖ ciphertext ‖ MAC`, the stored CRC is zeroed,
+    /// the encrypted bit is set and a `0x9901` extra field records the real method. For
+    /// ZipCrypto the 12-byte encryption header is prepended. An entry may not request both
+    /// schemes at once.
+    #[allow(unused_variables, unused_mut)]
+    fn encrypt_entry(
+        &self,
+        mut data: Vec<u8>,
+        mut crc: u32,
+        extra_fields: &mut ExtraFields,
+        compression_type: CompressionType,
+    ) -> std::io::Result<(Vec<u8>, u32, u16, Option<u16>)> {
+        #[cfg(all(feature = "aes", feature = "zipcrypto"))]
+        if self.encryption.is_some() && self.zipcrypto.is_some() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "an entry cannot use both AES and ZipCrypto encryption",
+            ));
+        }
+
+        let mut flags: u16 = 0;
+        let mut method_override: Option<u16> = None;
+
+        #[cfg(feature = "aes")]
+        if let Some(encryption) = &self.encryption {
+            data = Self::encrypt_ae2(data, encryption)?;
+            extra_fields.extend(ExtraFields::aes(encryption.strength.id(), compression_type));
+            crc = 0;
+            flags |= 0x0001;
+            method_override = Some(99);
+        }
+
+        #[cfg(feature = "zipcrypto")]
+        if let Some(password) = &self.zipcrypto {
+            data = Self::encrypt_zipcrypto(data, password, crc)?;
+            flags |= 0x0001;
+        }
+
+        Ok((data, crc, flags, method_override))
+    }
+
+    #[cfg(feature = "aes")]
+    fn encrypt_ae2(data: Vec<u8>, encryption: &AesEncryption) -> std::io::Result<Vec<u8>> {
+        use hmac::{Hmac, Mac};
+        use sha1::Sha1;
+
+        let strength = encryption.strength;
+        let key_len = strength.key_len();
+        let salt_len = key_len / 2;
+
+        let mut salt = vec![0u8; salt_len];
+        getrandom::getrandom(&mut salt)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+
+        // encryption key ‖ authentication key ‖ 2-byte password verifier
+        let mut derived = vec![0u8; key_len * 2 + 2];
+        pbkdf2::pbkdf2::<Hmac<Sha1>>(&encryption.password, &salt, 1000, &mut derived)
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "pbkdf2 failed"))?;
+        let enc_key = &derived[..key_len];
+        let auth_key = &derived[key_len..key_len * 2];
+        let verifier = &derived[key_len * 2..];
+
+        let mut ciphertext = data;
+        Self::aes_ctr_xor(strength, enc_key, &mut ciphertext);
+
+        let mut mac = Hmac::<Sha1>::new_from_slice(auth_key)
+            .expect("HMAC-SHA1 accepts keys of any length");
+        mac.update(&ciphertext);
+        let code = mac.finalize().into_bytes();
+
+        let mut out = Vec::with_capacity(salt_len + 2 + ciphertext.len() + 10);
+        out.extend_from_slice(&salt);
+        out.extend_from_slice(verifier);
+        out.extend_from_slice(&ciphertext);
+        out.extend_from_slice(&code[..10]);
+        Ok(out)
+    }
+
+    /// XORs `data` in place with the WinZip AES-CTR keystream (little-endian counter starting
+    /// at 1, one AES block per 16 bytes).
+    #[cfg(feature = "aes")]
+    fn aes_ctr_xor(strength: AesStrength, key: &[u8], data: &mut [u8]) {
+        use aes::cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit};
+
+        fn run<C: BlockEncrypt + KeyInit>(key: &[u8], data: &mut [u8]) {
+            let cipher = C::new(GenericArray::from_slice(key));
+            for (block_index, chunk) in data.chunks_mut(16).enumerate() {
+                let counter = ((block_index as u128) + 1).to_le_bytes();
+                let mut keystream = GenericArray::clone_from_slice(&counter);
+                cipher.encrypt_block(&mut keystream);
+                for (byte, k) in chunk.iter_mut().zip(keystream.iter()) {
+                    *byte ^= k;
+                }
+            }
+        }
+
+        match strength {
+            AesStrength::Aes128 => run::<aes::Aes128>(key, data),
+            AesStrength::Aes192 => run::<aes::Aes192>(key, data),
+            AesStrength::Aes256 => run::<aes::Aes256>(key, data),
+        }
+    }
+
+    /// Encrypts an already-compressed entry with the traditional PKWARE stream cipher, returning
+    /// the 12-byte encryption header followed by the ciphertext.
+    ///
+    /// Unlike AE-2, ZipCrypto keeps the real CRC in the header; its high byte doubles as the
+    /// password verifier. The caller sets general-purpose bit 0 (see [`encrypt_entry`]).
+    ///
+    /// [`encrypt_entry`]: Self::encrypt_entry
+    #[cfg(feature = "zipcrypto")]
+    fn encrypt_zipcrypto(data: Vec<u8>, password: &[u8], crc: u32) -> std::io::Result<Vec<u8>> {
+        let mut keys = ZipCryptoKeys::new(password);
+        let mut out = Vec::with_capacity(data.len() + 12);
+
+        // 12-byte header: 11 random bytes plus the CRC high byte as verifier.
+        let mut header = [0u8; 12];
+        getrandom::getrandom(&mut header[..11])
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        header[11] = (crc >> 24) as u8;
+
+        for &byte in header.iter().chain(data.iter()) {
+            out.push(byte ^ keys.stream_byte());
+            keys.update(byte);
+        }
+        Ok(out)
+    }
+
+    /// Returns the value to store in the 32-bit uncompressed-size header field, emitting a
+    /// `0x0001` Zip64 extra field (carrying the true 64-bit uncompressed and compressed sizes)
+    /// and the `0xFFFFFFFF` sentinel when either size no longer fits in 32 bits.
+    #[inline]
+    fn zip64_header_size(
+        uncompressed_size: u64,
+        compressed_size: u64,
+        extra_fields: &mut ExtraFields,
+    ) -> u32 {
+        if uncompressed_size > u32::MAX as u64 || compressed_size > u32::MAX as u64 {
+            extra_fields.extend(ExtraFields::zip64(uncompressed_size, compressed_size));
+            u32::MAX
+        } else {
+            uncompressed_size as u32
+        }
+    }
+
     pub fn into_file(self) -> std::io::Result<ZipFile> {
         match self.data_origin {
             ZipJobOrigin::Directory => Ok(ZipFile::directory(
@@ -111,12 +504,71 @@ impl ZipJob<'_, '_, '_> {
                 self.external_attributes,
                 self.file_comment,
             )),
-            ZipJobOrigin::Filesystem { path } => {
+            ZipJobOrigin::Filesystem {
+                path,
+                follow_symlinks,
+            } => {
+                #[cfg(not(unix))]
+                let _ = follow_symlinks;
+                // On Unix, preserve a symlink as a symlink: store the link target path as the
+                // (uncompressed, method 0) entry data and flag it with the S_IFLNK mode bits so
+                // that unzip and the `zip` crate restore it as a link.
+                #[cfg(unix)]
+                if !follow_symlinks {
+                    let symlink_metadata = std::fs::symlink_metadata(&path)?;
+                    if symlink_metadata.file_type().is_symlink() {
+                        use std::os::unix::ffi::OsStrExt;
+
+                        let target = std::fs::read_link(&path)?;
+                        let target_bytes = target.as_os_str().as_bytes();
+                        let FileDigest {
+                            data,
+                            uncompressed_size,
+                            crc,
+                        } = Self::compress_file(
+                            target_bytes,
+                            Some(target_bytes.len() as u64),
+                            CompressionType::Stored,
+                            self.compression_level,
+                        )?;
+                        // Record the link's own lstat metadata, as the non-symlink path below does
+                        // for the opened file's metadata, and honour
+                        // any requested encryption of the stored target path.
+                        let mut extra_fields = ExtraFields::new_from_fs(&symlink_metadata);
+                        extra_fields.extend(self.extra_fields);
+                        let (data, crc, flags, method_override) = self.encrypt_entry(
+                            data,
+                            crc,
+                            &mut extra_fields,
+                            CompressionType::Stored,
+                        )?;
+                        let uncompressed_size = Self::zip64_header_size(
+                            uncompressed_size,
+                            data.len() as u64,
+                            &mut extra_fields,
+                        );
+                        // S_IFLNK | 0o777
+                        let external_file_attributes: u32 = 0o120_777;
+                        return Ok(ZipFile {
+                            header: ZipFileHeader {
+                                compression_type: CompressionType::Stored,
+                                flags,
+                                method_override,
+                                crc,
+                                uncompressed_size,
+                                filename: self.archive_path,
+                                external_file_attributes: external_file_attributes << 16,
+                                extra_fields,
+                                file_comment: self.file_comment,
+                            },
+                            data,
+                        });
+                    }
+                }
+
                 let file = File::open(path).unwrap();
                 let file_metadata = file.metadata().unwrap();
                 let uncompressed_size_approx = file_metadata.len();
-                debug_assert!(uncompressed_size_approx <= u32::MAX.into());
-                let uncompressed_size_approx = uncompressed_size_approx as u32;
                 let external_file_attributes = Self::attributes_from_fs(&file_metadata);
                 let mut extra_fields = ExtraFields::new_from_fs(&file_metadata);
                 extra_fields.extend(self.extra_fields);
@@ -131,9 +583,18 @@ impl ZipJob<'_, '_, '_> {
                     self.compression_type,
                     self.compression_level,
                 )?;
+                let (data, crc, flags, method_override) =
+                    self.encrypt_entry(data, crc, &mut extra_fields, self.compression_type)?;
+                let uncompressed_size = Self::zip64_header_size(
+                    uncompressed_size,
+                    data.len() as u64,
+                    &mut extra_fields,
+                );
                 Ok(ZipFile {
                     header: ZipFileHeader {
-                        compression_type: CompressionType::Deflate,
+                        compression_type: self.compression_type,
+                        flags,
+                        method_override,
                         crc,
                         uncompressed_size,
                         filename: self.archive_path,
@@ -145,9 +606,7 @@ impl ZipJob<'_, '_, '_> {
                 })
             }
             ZipJobOrigin::RawData(data) => {
-                let uncompressed_size_approx = data.len();
-                debug_assert!(uncompressed_size_approx <= u32::MAX as usize);
-                let uncompressed_size_approx = uncompressed_size_approx as u32;
+                let uncompressed_size_approx = data.len() as u64;
 
                 let FileDigest {
                     data,
@@ -159,14 +618,24 @@ impl ZipJob<'_, '_, '_> {
                     self.compression_type,
                     self.compression_level,
                 )?;
+                let mut extra_fields = self.extra_fields;
+                let (data, crc, flags, method_override) =
+                    self.encrypt_entry(data, crc, &mut extra_fields, self.compression_type)?;
+                let uncompressed_size = Self::zip64_header_size(
+                    uncompressed_size,
+                    data.len() as u64,
+                    &mut extra_fields,
+                );
                 Ok(ZipFile {
                     header: ZipFileHeader {
-                        compression_type: CompressionType::Deflate,
+                        compression_type: self.compression_type,
+                        flags,
+                        method_override,
                         crc,
                         uncompressed_size,
                         filename: self.archive_path,
                         external_file_attributes: (self.external_attributes as u32) << 16,
-                        extra_fields: self.extra_fields,
+                        extra_fields,
                         file_comment: self.file_comment,
                     },
                     data,
@@ -183,14 +652,24 @@ impl ZipJob<'_, '_, '_> {
                     self.compression_type,
                     self.compression_level,
                 )?;
+                let mut extra_fields = self.extra_fields;
+                let (data, crc, flags, method_override) =
+                    self.encrypt_entry(data, crc, &mut extra_fields, self.compression_type)?;
+                let uncompressed_size = Self::zip64_header_size(
+                    uncompressed_size,
+                    data.len() as u64,
+                    &mut extra_fields,
+                );
                 Ok(ZipFile {
                     header: ZipFileHeader {
-                        compression_type: CompressionType::Deflate,
+                        compression_type: self.compression_type,
+                        flags,
+                        method_override,
                         crc,
                         uncompressed_size,
                         filename: self.archive_path,
                         external_file_attributes: (self.external_attributes as u32) << 16,
-                        extra_fields: self.extra_fields,
+                        extra_fields,
                         file_comment: self.file_comment,
                     },
                     data,
@@ -198,4 +677,89 @@ impl ZipJob<'_, '_, '_> {
             }
         }
     }
+
+    /// Streams an entry's compressed data straight into `sink` without buffering it, returning
+    /// a header with its CRC and size fields zeroed (to be written with general-purpose bit 3
+    /// set) plus the matching data descriptor for the central directory to backfill. Use this
+    /// instead of [`into_file`](Self::into_file) for inputs too large to hold in memory.
+    ///
+    /// Streaming does not support encryption. Rather than silently drop a requested password, a
+    /// job that carries one is rejected with
+    /// [`ErrorKind::InvalidInput`](std::io::ErrorKind::InvalidInput). A streamed
+    /// [`ZipJobOrigin::Filesystem`] always follows symlinks (storing the target's contents),
+    /// whatever its `follow_symlinks` says, unlike [`into_file`](Self::into_file), which can
+    /// preserve them.
+    pub fn into_stream<W: Write>(
+        self,
+        sink: &mut W,
+    ) -> std::io::Result<(ZipFileHeader, ZipFileDataDescriptor)> {
+        #[cfg(feature = "aes")]
+        if self.encryption.is_some() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "encryption is not supported on the streaming path",
+            ));
+        }
+        #[cfg(feature = "zipcrypto")]
+        if self.zipcrypto.is_some() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "encryption is not supported on the streaming path",
+            ));
+        }
+
+        let (descriptor, external_file_attributes, extra_fields) = match self.data_origin {
+            ZipJobOrigin::Directory => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "cannot stream a directory entry",
+                ))
+            }
+            ZipJobOrigin::Filesystem { path, .. } => {
+                let file = File::open(path)?;
+                let file_metadata = file.metadata()?;
+                let external_file_attributes = Self::attributes_from_fs(&file_metadata);
+                let mut extra_fields = ExtraFields::new_from_fs(&file_metadata);
+                extra_fields.extend(self.extra_fields);
+                let descriptor = Self::compress_stream(
+                    file,
+                    self.compression_type,
+                    self.compression_level,
+                    sink,
+                )?;
+                (descriptor, external_file_attributes, extra_fields)
+            }
+            ZipJobOrigin::RawData(data) => {
+                let descriptor = Self::compress_stream(
+                    data.as_ref(),
+                    self.compression_type,
+                    self.compression_level,
+                    sink,
+                )?;
+                (descriptor, self.external_attributes, self.extra_fields)
+            }
+            ZipJobOrigin::Reader(reader) => {
+                let descriptor = Self::compress_stream(
+                    reader,
+                    self.compression_type,
+                    self.compression_level,
+                    sink,
+                )?;
+                (descriptor, self.external_attributes, self.extra_fields)
+            }
+        };
+        let header = ZipFileHeader {
+            compression_type: self.compression_type,
+            // General-purpose bit 3: CRC and sizes follow in a trailing data descriptor.
+            flags: 0x0008,
+            method_override: None,
+            crc: 0,
+            uncompressed_size: 0,
+            filename: self.archive_path,
+            external_file_attributes: (external_file_attributes as u32) << 16,
+            extra_fields,
+            file_comment: self.file_comment,
+        };
+        Ok((header, descriptor))
+    }
 }